@@ -1,7 +1,12 @@
 use clap::{Arg, Command, ArgAction, ArgGroup};
+use std::collections::VecDeque;
 use std::fs::{self, OpenOptions};
 use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::UNIX_EPOCH;
 
 /// Inserts, appends, or clears text in one or more files based on command-line arguments.
 ///
@@ -28,12 +33,24 @@ fn main() -> io::Result<()> {
              Interactively insert text at line 2:\n\
              \t$ echo \"New Line\" | it -i -l 2 -I file.txt\n\n\
              Create a backup before modifying multiple files:\n\
-             \t$ it -b -a \"Appended\" file1.txt file2.txt"
+             \t$ it -b -a \"Appended\" file1.txt file2.txt\n\n\
+             List the backup snapshots kept for file.txt:\n\
+             \t$ it --list-versions file.txt\n\n\
+             Restore file.txt from backup snapshot 2:\n\
+             \t$ it --restore file.txt:2\n\n\
+             Append to many files at once, four at a time:\n\
+             \t$ it --jobs 4 -a \"x\" *.log\n\n\
+             Insert 'X' at byte offset 10 in file.bin:\n\
+             \t$ it --byte --at 10 -i \"X\" file.bin\n\n\
+             Overwrite the last 4 bytes of file.bin:\n\
+             \t$ it --byte --at 4 --from-end -o -i \"DONE\" file.bin\n\n\
+             Append and confirm the write landed on disk, restoring the backup if not:\n\
+             \t$ it -b --verify -a \"Appended\" file.txt"
         )
         .arg(
             Arg::new("file")
                 .help("The file(s) to modify")
-                .required(true)
+                .required(false)
                 .num_args(1..)
                 .index(1),
         )
@@ -96,9 +113,67 @@ fn main() -> io::Result<()> {
             Arg::new("backup")
                 .short('b')
                 .long("backup")
-                .help("Create a backup of the original file (adds .bak extension)")
+                .help("Create a numbered backup snapshot of the file before modifying it")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("keep")
+                .long("keep")
+                .value_name("N")
+                .help("Maximum number of backup snapshots to retain per file")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("10"),
+        )
+        .arg(
+            Arg::new("list-versions")
+                .long("list-versions")
+                .value_name("FILE")
+                .help("List the backup snapshots kept for FILE and exit")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("restore")
+                .long("restore")
+                .value_name("FILE[:N]")
+                .help("Restore FILE from backup snapshot N (default: the latest) and exit")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("byte")
+                .long("byte")
+                .help("Address --at/--insert/--overwrite/--clear by byte offset instead of line number")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("at")
+                .long("at")
+                .value_name("BYTE")
+                .help("Byte offset to insert/overwrite at in --byte mode (default: 0)")
+                .requires("byte")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("from-end")
+                .long("from-end")
+                .help("Treat --at as an offset back from the end of the file")
+                .requires("at")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verify")
+                .long("verify")
+                .help("Read the file back after writing and confirm it matches exactly")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .help("Process up to N files concurrently")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
         .arg(
             Arg::new("interactive")
                 .short('I')
@@ -120,166 +195,629 @@ fn main() -> io::Result<()> {
         )
         .get_matches();
 
-    let file_paths = matches.get_many::<String>("file").unwrap();
-    let line_num = matches.get_one::<usize>("line").copied();
-    let overwrite = matches.get_flag("overwrite");
+    if let Some(target) = matches.get_one::<String>("list-versions") {
+        return list_versions(target);
+    }
+    if let Some(spec) = matches.get_one::<String>("restore") {
+        return restore_version(spec);
+    }
+
+    let file_paths: Vec<String> = matches
+        .get_many::<String>("file")
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the following required arguments were not provided: <file>...",
+            )
+        })?
+        .cloned()
+        .collect();
+
+    let interactive = matches.get_flag("interactive");
     let insert_text = matches.get_one::<String>("insert").cloned();
     let append_text = matches.get_one::<String>("append").cloned();
     let clear_range = matches.get_one::<(usize, Option<usize>)>("clear").cloned();
-    let backup = matches.get_flag("backup");
-    let interactive = matches.get_flag("interactive");
-    let dry_run = matches.get_flag("dry-run");
 
-    for file_path in file_paths {
-        // Validate file path
-        if Path::new(file_path).is_dir() {
+    // Interactive text is read from stdin once, up front, and shared across every file in the
+    // batch; stdin can't be split meaningfully across concurrent workers.
+    let stdin_text = if interactive && clear_range.is_none() {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Some(input.trim_end().to_string())
+    } else {
+        None
+    };
+    let insert_text = insert_text.or_else(|| stdin_text.clone());
+    let append_text = append_text.or(stdin_text);
+
+    let opts = Arc::new(Options {
+        line_num: matches.get_one::<usize>("line").copied(),
+        overwrite: matches.get_flag("overwrite"),
+        insert_text,
+        append_text,
+        clear_range,
+        backup: matches.get_flag("backup"),
+        keep: *matches.get_one::<usize>("keep").unwrap(),
+        dry_run: matches.get_flag("dry-run"),
+        byte_mode: matches.get_flag("byte"),
+        at: matches.get_one::<usize>("at").copied(),
+        from_end: matches.get_flag("from-end"),
+        verify: matches.get_flag("verify"),
+    });
+    let jobs = (*matches.get_one::<usize>("jobs").unwrap()).max(1);
+
+    let results = process_files(file_paths, jobs, opts);
+
+    let mut failed = false;
+    for (file_path, result) in results {
+        if let Err(e) = result {
+            eprintln!("it: '{}': {}", file_path, e);
+            failed = true;
+        }
+    }
+
+    if failed {
+        Err(io::Error::other("one or more files failed to process"))
+    } else {
+        Ok(())
+    }
+}
+
+/// The operation to apply to every file in the batch, parsed once from the command line.
+///
+/// Derives `Default` so tests can build one with `Options { field: ..., ..Default::default() }`
+/// instead of repeating every field; `main` always builds a full literal from `matches` instead.
+#[derive(Default)]
+struct Options {
+    line_num: Option<usize>,
+    overwrite: bool,
+    insert_text: Option<String>,
+    append_text: Option<String>,
+    clear_range: Option<(usize, Option<usize>)>,
+    backup: bool,
+    keep: usize,
+    dry_run: bool,
+    byte_mode: bool,
+    at: Option<usize>,
+    from_end: bool,
+    verify: bool,
+}
+
+/// Runs `process_file` over `file_paths`, spreading the work across up to `jobs` worker threads.
+///
+/// Each worker pulls the next file off a shared queue, so a slow file doesn't stall the others.
+/// Results are collected per file rather than bailing out on the first error, so one bad file in
+/// a large batch doesn't stop the rest from being processed.
+fn process_files(file_paths: Vec<String>, jobs: usize, opts: Arc<Options>) -> Vec<(String, io::Result<()>)> {
+    let queue = Arc::new(Mutex::new(file_paths.into_iter().collect::<VecDeque<String>>()));
+    let results = Arc::new(Mutex::new(Vec::new()));
+
+    let handles: Vec<_> = (0..jobs)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let results = Arc::clone(&results);
+            let opts = Arc::clone(&opts);
+            thread::spawn(move || loop {
+                let file_path = match queue.lock().unwrap().pop_front() {
+                    Some(file_path) => file_path,
+                    None => break,
+                };
+                let result = process_file(&file_path, &opts);
+                results.lock().unwrap().push((file_path, result));
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    Arc::try_unwrap(results).unwrap().into_inner().unwrap()
+}
+
+/// Applies `opts` to a single file: validates it, takes a backup snapshot if requested, performs
+/// the insert/append/clear operation, and commits the result with `commit` (an atomic write). A
+/// plain `--append` without `--verify` is the exception: it appends to the file in place.
+fn process_file(file_path: &str, opts: &Options) -> io::Result<()> {
+    // Validate file path
+    if Path::new(file_path).is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' is a directory, not a file.", file_path),
+        ));
+    }
+    if Path::new(file_path).exists() {
+        let metadata = fs::metadata(file_path).map_err(|e| {
+            io::Error::other(format!("Cannot access '{}': {}", file_path, e))
+        })?;
+        if metadata.permissions().readonly() {
             return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("'{}' is a directory, not a file.", file_path),
+                io::ErrorKind::PermissionDenied,
+                format!("No write permission for '{}'.", file_path),
             ));
         }
-        if Path::new(file_path).exists() {
-            let metadata = fs::metadata(file_path).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Cannot access '{}': {}", file_path, e),
-                )
-            })?;
-            if metadata.permissions().readonly() {
-                return Err(io::Error::new(
-                    io::ErrorKind::PermissionDenied,
-                    format!("No write permission for '{}'.", file_path),
-                ));
-            }
-        }
+    }
 
-        // Handle interactive mode
-        let insert_text = if interactive && insert_text.is_none() && clear_range.is_none() {
-            let mut input = String::new();
-            io::stdin().read_to_string(&mut input)?;
-            Some(input.trim_end().to_string())
-        } else {
-            insert_text.clone()
-        };
-        let append_text = if interactive && append_text.is_none() && clear_range.is_none() {
-            let mut input = String::new();
-            io::stdin().read_to_string(&mut input)?;
-            Some(input.trim_end().to_string())
-        } else {
-            append_text.clone()
-        };
+    // Hold an advisory lock for the rest of the read-modify-write so concurrent `it` invocations
+    // (or concurrent workers in this batch) don't race on the same file. Skipped in --dry-run,
+    // which never touches the file and shouldn't leave a lock sidecar behind either.
+    let _lock = if opts.dry_run { None } else { Some(FileLock::acquire(file_path)?) };
 
-        // Create backup if requested
-        if backup && Path::new(file_path).exists() {
-            let backup_path = format!("{}.bak", file_path);
-            fs::copy(file_path, &backup_path).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Failed to create backup '{}': {}", backup_path, e),
-                )
-            })?;
-        }
+    // Create a numbered backup snapshot if requested
+    if opts.backup && Path::new(file_path).exists() {
+        create_snapshot(file_path, opts.keep).map_err(|e| {
+            io::Error::other(format!("Failed to create backup snapshot for '{}': {}", file_path, e))
+        })?;
+    }
 
-        // Handle append operation efficiently
-        if let Some(text) = &append_text {
-            if !dry_run {
-                let mut file = OpenOptions::new()
-                    .write(true)
-                    .append(true)
-                    .create(true)
-                    .open(file_path)?;
-                writeln!(file, "{}", text)?;
-            } else {
-                let mut content = String::new();
-                if Path::new(file_path).exists() {
-                    fs::File::open(file_path)?.read_to_string(&mut content)?;
-                }
-                let mut lines: Vec<String> = content.lines().map(String::from).collect();
-                lines.push(text.to_string());
-                println!("{}", lines.join("\n"));
+    if opts.byte_mode {
+        return process_file_bytes(file_path, opts);
+    }
+
+    // Handle append operation
+    if let Some(text) = &opts.append_text {
+        if opts.dry_run {
+            let mut content = String::new();
+            if Path::new(file_path).exists() {
+                fs::File::open(file_path)?.read_to_string(&mut content)?;
             }
-            continue;
+            let mut lines: Vec<String> = content.lines().map(String::from).collect();
+            lines.push(text.to_string());
+            println!("{}", lines.join("\n"));
+            return Ok(());
         }
 
-        // Read the file content for other operations
-        let mut content = String::new();
+        if !opts.verify {
+            // Fast path: append in place without reading the rest of the file. Skipped when
+            // --verify is set, since verifying requires knowing the exact bytes we intended to
+            // write, and this path never builds them.
+            let mut file = OpenOptions::new().append(true).create(true).open(file_path)?;
+            writeln!(file, "{}", text)?;
+            return Ok(());
+        }
+
+        let mut data = Vec::new();
         if Path::new(file_path).exists() {
-            let mut file = fs::File::open(file_path)?;
-            file.read_to_string(&mut content)?;
+            fs::File::open(file_path)?.read_to_end(&mut data)?;
         }
+        data.extend_from_slice(text.as_bytes());
+        data.push(b'\n');
+        commit(file_path, &data, opts)?;
+        return Ok(());
+    }
+
+    // Read the file content for other operations
+    let mut content = String::new();
+    if Path::new(file_path).exists() {
+        let mut file = fs::File::open(file_path)?;
+        file.read_to_string(&mut content)?;
+    }
 
-        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    // Perform the operation
+    if let Some((start, end)) = opts.clear_range {
+        // Clear mode: clear from start to end (or end of file)
+        let start_idx = start.saturating_sub(1);
+        if start_idx >= lines.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Start line {} is beyond file length for '{}'.", start, file_path),
+            ));
+        }
+        let end_idx = end.unwrap_or(lines.len());
+        if end_idx > lines.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("End line {} is beyond file length for '{}'.", end_idx, file_path),
+            ));
+        }
+        lines.drain(start_idx..end_idx);
         if lines.is_empty() {
             lines.push(String::new());
         }
+    } else if let Some(text) = &opts.insert_text {
+        // Insert mode: insert or overwrite at specified line
+        let insert_line = opts.line_num.unwrap_or(1).saturating_sub(1);
+        if insert_line >= lines.len() {
+            lines.resize(insert_line + 1, String::new());
+        }
+        if opts.overwrite {
+            lines[insert_line] = text.to_string();
+        } else {
+            lines.insert(insert_line, text.to_string());
+        }
+    } else if opts.line_num.is_some() || opts.overwrite {
+        // Insert or overwrite empty line if no text provided
+        let insert_line = opts.line_num.unwrap_or(1).saturating_sub(1);
+        if insert_line >= lines.len() {
+            lines.resize(insert_line + 1, String::new());
+        }
+        if opts.overwrite {
+            lines[insert_line] = String::new();
+        } else {
+            lines.insert(insert_line, String::new());
+        }
+    } else {
+        // Default behavior: append an empty line
+        lines.push(String::new());
+    }
 
-        // Perform the operation
-        if let Some((start, end)) = clear_range {
-            // Clear mode: clear from start to end (or end of file)
-            let start_idx = start.saturating_sub(1);
-            if start_idx >= lines.len() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Start line {} is beyond file length for '{}'.", start, file_path),
-                ));
-            }
-            let end_idx = end.unwrap_or(lines.len());
-            if end_idx > lines.len() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("End line {} is beyond file length for '{}'.", end_idx, file_path),
-                ));
-            }
-            lines.drain(start_idx..end_idx);
-            if lines.is_empty() {
-                lines.push(String::new());
-            }
-        } else if let Some(text) = insert_text {
-            // Insert mode: insert or overwrite at specified line
-            let insert_line = line_num.unwrap_or(1).saturating_sub(1);
-            if insert_line >= lines.len() {
-                lines.resize(insert_line + 1, String::new());
-            }
-            if overwrite {
-                lines[insert_line] = text.to_string();
-            } else {
-                lines.insert(insert_line, text.to_string());
+    // Write or display the result
+    if opts.dry_run {
+        println!("{}", lines.join("\n"));
+    } else {
+        let mut data = lines.join("\n").into_bytes();
+        if !lines.is_empty() && !content.ends_with('\n') {
+            data.push(b'\n');
+        }
+        commit(file_path, &data, opts)?;
+    }
+
+    Ok(())
+}
+
+/// Applies `opts` to `file_path` by byte offset rather than by line, for `--byte` mode.
+///
+/// This works on the raw bytes of the file instead of round-tripping through `content.lines()`,
+/// so it doesn't lose information on files with unusual line endings, no trailing newline, or
+/// binary content. `--clear START,END` removes the 1-indexed, inclusive byte range `[START, END]`
+/// (so `-z 2,3` removes bytes 2 and 3); `-i`/`-o` splice or overwrite the text at `--at` (or byte
+/// `0` if `--at` is omitted); `-a` always appends at the current end of the file and ignores
+/// `--at`/`--from-end`.
+fn process_file_bytes(file_path: &str, opts: &Options) -> io::Result<()> {
+    let mut content = Vec::new();
+    if Path::new(file_path).exists() {
+        fs::File::open(file_path)?.read_to_end(&mut content)?;
+    }
+
+    if let Some((start, end)) = opts.clear_range {
+        let start_idx = start.saturating_sub(1);
+        if start_idx >= content.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Start byte {} is beyond the end of '{}'.", start, file_path),
+            ));
+        }
+        let end_idx = end.unwrap_or(content.len());
+        if end_idx > content.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("End byte {} is beyond the end of '{}'.", end_idx, file_path),
+            ));
+        }
+        content.drain(start_idx..end_idx);
+    } else if let Some(text) = &opts.append_text {
+        // --append always means "at the end of the file"; --at/--from-end only apply to --insert.
+        content.extend_from_slice(text.as_bytes());
+    } else {
+        let text = opts.insert_text.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--byte mode requires --insert, --append, or --clear",
+            )
+        })?;
+        let pos = resolve_byte_offset(opts.at.unwrap_or(0), opts.from_end, content.len())?;
+        if pos > content.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Byte offset {} is beyond the end of '{}'.", pos, file_path),
+            ));
+        }
+
+        let text_bytes = text.as_bytes();
+        if opts.overwrite {
+            let end = pos + text_bytes.len();
+            if end > content.len() {
+                content.resize(end, 0);
             }
-        } else if line_num.is_some() || overwrite {
-            // Insert or overwrite empty line if no text provided
-            let insert_line = line_num.unwrap_or(1).saturating_sub(1);
-            if insert_line >= lines.len() {
-                lines.resize(insert_line + 1, String::new());
+            content[pos..end].copy_from_slice(text_bytes);
+        } else {
+            content.splice(pos..pos, text_bytes.iter().copied());
+        }
+    }
+
+    if opts.dry_run {
+        io::stdout().write_all(&content)?;
+    } else {
+        commit(file_path, &content, opts)?;
+    }
+    Ok(())
+}
+
+/// Resolves `--at`/`--from-end` into an absolute byte offset into a file of length `len`.
+fn resolve_byte_offset(at: usize, from_end: bool, len: usize) -> io::Result<usize> {
+    if from_end {
+        len.checked_sub(at).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("--at {} --from-end exceeds the file length ({} bytes)", at, len),
+            )
+        })
+    } else {
+        Ok(at)
+    }
+}
+
+/// Raw `flock(2)` binding used by `FileLock`. Kept as a direct `extern "C"` declaration rather
+/// than a dependency since nothing else in this crate needs a libc binding.
+mod flock_sys {
+    use std::os::raw::c_int;
+
+    pub const LOCK_EX: c_int = 2;
+
+    extern "C" {
+        pub fn flock(fd: c_int, operation: c_int) -> c_int;
+    }
+}
+
+/// An exclusive lock on `file_path`, backed by a `.lock` sidecar file and a real `flock(2)`
+/// exclusive lock on its file descriptor.
+///
+/// The lock lives on the open file description, not on the sidecar's mere existence, so the
+/// kernel releases it automatically if the holding process dies or is killed — a crashed `it`
+/// can never leave a stale lock behind. Acquiring blocks until any previous holder releases it,
+/// so concurrent workers racing on the same path (including a duplicate path within the same
+/// `--jobs` batch) are correctly serialized instead of spuriously failing.
+///
+/// On a clean release, the sidecar is unlinked so a long-lived batch (e.g. `it -a x *.log`)
+/// doesn't leave one `.lock` file behind per target forever. A waiter that wakes up holding a
+/// lock on a sidecar inode the previous holder already unlinked drops it and retries on the
+/// current path, and the unlink on release only happens if the path still points at *our*
+/// inode, so a release never deletes a sidecar some later holder has recreated.
+struct FileLock {
+    lock_path: String,
+    file: fs::File,
+}
+
+impl FileLock {
+    fn acquire(file_path: &str) -> io::Result<FileLock> {
+        use std::os::unix::fs::MetadataExt;
+        let lock_path = format!("{}.lock", file_path);
+        loop {
+            let file = OpenOptions::new().write(true).create(true).truncate(false).open(&lock_path)?;
+            if unsafe { flock_sys::flock(file.as_raw_fd(), flock_sys::LOCK_EX) } != 0 {
+                return Err(io::Error::last_os_error());
             }
-            if overwrite {
-                lines[insert_line] = String::new();
-            } else {
-                lines.insert(insert_line, String::new());
+            // The previous holder may have unlinked the sidecar while we waited, leaving us locked
+            // on an orphaned inode; only keep the lock if the path still points at our inode.
+            let held = file.metadata()?;
+            match fs::metadata(&lock_path) {
+                Ok(current) if current.dev() == held.dev() && current.ino() == held.ino() => {
+                    return Ok(FileLock { lock_path, file });
+                }
+                _ => continue,
             }
-        } else {
-            // Default behavior: append an empty line
-            lines.push(String::new());
         }
+    }
+}
 
-        // Write or display the result
-        if dry_run {
-            println!("{}", lines.join("\n"));
-        } else {
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(file_path)?;
-            file.write_all(lines.join("\n").as_bytes())?;
-            if !lines.is_empty() && !content.ends_with('\n') {
-                file.write_all(b"\n")?;
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+        let same_inode = match (self.file.metadata(), fs::metadata(&self.lock_path)) {
+            (Ok(held), Ok(current)) => held.dev() == current.dev() && held.ino() == current.ino(),
+            _ => false,
+        };
+        if same_inode {
+            let _ = fs::remove_file(&self.lock_path);
+        }
+    }
+}
+
+/// Writes `data` to `path` without ever leaving a truncated or partially-written file behind.
+///
+/// The new contents are written to a temporary sibling file in the same directory, flushed to
+/// disk, and then moved into place with `rename`, which is atomic on the same filesystem. If
+/// anything fails before the rename, the temporary file is removed and the original is left
+/// untouched. If `path` already exists, its permissions (and owner, where privileges allow it)
+/// are copied onto the temporary file before the rename, so an atomic write can't silently widen
+/// a file's permissions the way a fresh `create(true)` open otherwise would.
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("'{}' has no file name", path.display()))
+    })?;
+    let tmp_path = dir.join(format!("{}.it-tmp-{}", file_name.to_string_lossy(), std::process::id()));
+    let existing_metadata = fs::metadata(path).ok();
+
+    let result = (|| -> io::Result<()> {
+        let mut tmp_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        if let Some(metadata) = &existing_metadata {
+            fs::set_permissions(&tmp_path, metadata.permissions())?;
+            preserve_owner(&tmp_path, metadata);
+        }
+        tmp_file.write_all(data)?;
+        tmp_file.sync_all()
+    })();
+
+    match result {
+        Ok(()) => fs::rename(&tmp_path, path),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Best-effort `chown` of `tmp_path` to `metadata`'s owner. Only root can actually change a
+/// file's owner to someone else, so a permission failure here is expected and not fatal — unlike
+/// the mode bits, which `write_atomic` propagates as a hard error.
+fn preserve_owner(tmp_path: &Path, metadata: &fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+    let _ = std::os::unix::fs::chown(tmp_path, Some(metadata.uid()), Some(metadata.gid()));
+}
+
+/// Writes `data` to `path` via `write_atomic`, then, if `verify` is set, re-reads the file and
+/// confirms the on-disk bytes exactly match `data`, guarding against silent corruption from full
+/// disks, flaky storage, or encoding surprises.
+fn write_verified(path: &Path, data: &[u8], verify: bool) -> io::Result<()> {
+    write_atomic(path, data)?;
+    if verify {
+        let on_disk = fs::read(path)?;
+        if on_disk != data {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Verification failed for '{}': wrote {} byte(s) but read back {} that don't match.",
+                    path.display(),
+                    data.len(),
+                    on_disk.len()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Commits `data` to `file_path` with `write_verified`. If that fails (the write itself or its
+/// read-back verification) while both `--verify` and `--backup` are set, the file is automatically
+/// restored from the latest snapshot before the original error is returned.
+fn commit(file_path: &str, data: &[u8], opts: &Options) -> io::Result<()> {
+    match write_verified(Path::new(file_path), data, opts.verify) {
+        Ok(()) => Ok(()),
+        Err(e) if opts.verify && opts.backup => match restore_snapshot(file_path, None) {
+            Ok(n) => Err(io::Error::new(e.kind(), format!("{} Restored snapshot {}.", e, n))),
+            Err(restore_err) => {
+                Err(io::Error::new(e.kind(), format!("{} Auto-restore also failed: {}", e, restore_err)))
+            }
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the path of backup snapshot number `n` for `file_path` (e.g. `file.txt.it.3`).
+fn snapshot_path(file_path: &str, n: usize) -> String {
+    format!("{}.it.{}", file_path, n)
+}
+
+/// Returns the snapshot numbers currently kept for `file_path`, sorted oldest first.
+fn snapshot_numbers(file_path: &str) -> io::Result<Vec<usize>> {
+    let path = Path::new(file_path);
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let file_name = match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => return Ok(Vec::new()),
+    };
+    let prefix = format!("{}.it.", file_name);
+
+    let mut numbers = Vec::new();
+    if !dir.exists() {
+        return Ok(numbers);
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if let Some(suffix) = name.strip_prefix(&prefix) {
+            if let Ok(n) = suffix.parse::<usize>() {
+                numbers.push(n);
             }
         }
     }
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/// Copies `file_path` into a new numbered snapshot, pruning the oldest snapshots beyond `keep`.
+fn create_snapshot(file_path: &str, keep: usize) -> io::Result<()> {
+    let mut numbers = snapshot_numbers(file_path)?;
+    let next = numbers.last().copied().unwrap_or(0) + 1;
+    fs::copy(file_path, snapshot_path(file_path, next))?;
+    numbers.push(next);
+
+    while numbers.len() > keep {
+        let oldest = numbers.remove(0);
+        let _ = fs::remove_file(snapshot_path(file_path, oldest));
+    }
+    Ok(())
+}
 
+/// Implements `--list-versions FILE`: prints each snapshot's index, modified time, and line count.
+fn list_versions(file_path: &str) -> io::Result<()> {
+    let numbers = snapshot_numbers(file_path)?;
+    if numbers.is_empty() {
+        println!("No backup snapshots found for '{}'.", file_path);
+        return Ok(());
+    }
+    println!("{:>5}  {:<19}  {:>8}", "INDEX", "MODIFIED", "LINES");
+    for n in numbers {
+        let snap = snapshot_path(file_path, n);
+        let metadata = fs::metadata(&snap)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let line_count = fs::read_to_string(&snap)?.lines().count();
+        println!("{:>5}  {:<19}  {:>8}", n, format_unix_time(modified), line_count);
+    }
     Ok(())
 }
 
+/// Formats a Unix timestamp (seconds since the epoch) as `YYYY-MM-DD HH:MM:SS` UTC.
+///
+/// This tool's only dependency is `clap`, so rather than pull in a date/time crate just for
+/// `--list-versions`, the civil date is computed directly with Howard Hinnant's `civil_from_days`
+/// algorithm: <http://howardhinnant.github.io/date_algorithms.html>.
+fn format_unix_time(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let time_of_day = secs % 86400;
+    let (hour, minute, second) = (time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60);
+
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let year = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { year + 1 } else { year };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, hour, minute, second)
+}
+
+/// Implements `--restore FILE[:N]`: copies snapshot `N` (default: the latest) back over `FILE`.
+fn restore_version(spec: &str) -> io::Result<()> {
+    let (file_path, requested) = match spec.rsplit_once(':') {
+        Some((file_path, n)) if n.parse::<usize>().is_ok() => (file_path, Some(n.parse::<usize>().unwrap())),
+        _ => (spec, None),
+    };
+
+    let n = restore_snapshot(file_path, requested)?;
+    println!("Restored '{}' from snapshot {}.", file_path, n);
+    Ok(())
+}
+
+/// Copies backup snapshot `requested` (default: the latest) back over `file_path`, returning the
+/// snapshot number that was restored. Used by both `--restore` and `--verify`'s auto-restore.
+fn restore_snapshot(file_path: &str, requested: Option<usize>) -> io::Result<usize> {
+    let numbers = snapshot_numbers(file_path)?;
+    let n = requested.or_else(|| numbers.last().copied()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No backup snapshots found for '{}'.", file_path),
+        )
+    })?;
+
+    let snap = snapshot_path(file_path, n);
+    if !Path::new(&snap).exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Snapshot {} not found for '{}'.", n, file_path),
+        ));
+    }
+
+    let data = fs::read(&snap)?;
+    write_atomic(Path::new(file_path), &data)?;
+    Ok(n)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -316,4 +854,311 @@ mod tests {
         lines.drain(1..); // Clear from line 2 to end
         assert_eq!(lines, vec!["Line 1"]);
     }
+
+    /// Tests that an atomic write leaves the target containing the new data and no stray
+    /// temp file behind.
+    #[test]
+    fn test_write_atomic_replaces_contents() {
+        let path = std::env::temp_dir().join(format!("it-test-write-atomic-{}.txt", std::process::id()));
+        fs::write(&path, b"old contents").unwrap();
+
+        write_atomic(&path, b"new contents").unwrap();
+
+        let mut result = String::new();
+        fs::File::open(&path).unwrap().read_to_string(&mut result).unwrap();
+        assert_eq!(result, "new contents");
+
+        let tmp_path = path.with_file_name(format!(
+            "{}.it-tmp-{}",
+            path.file_name().unwrap().to_string_lossy(),
+            std::process::id()
+        ));
+        assert!(!tmp_path.exists());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that an atomic write preserves the target's existing permission bits rather than
+    /// letting the temp file's `create(true)` open pick fresh umask-default permissions.
+    #[test]
+    fn test_write_atomic_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("it-test-write-atomic-perms-{}.txt", std::process::id()));
+        fs::write(&path, b"old contents").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, b"new contents").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that snapshots beyond `keep` are pruned oldest-first.
+    #[test]
+    fn test_create_snapshot_prunes_oldest() {
+        let path = std::env::temp_dir().join(format!("it-test-snapshot-{}.txt", std::process::id()));
+        fs::write(&path, b"v1").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        create_snapshot(file_path, 2).unwrap();
+        create_snapshot(file_path, 2).unwrap();
+        create_snapshot(file_path, 2).unwrap();
+
+        assert_eq!(snapshot_numbers(file_path).unwrap(), vec![2, 3]);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(snapshot_path(file_path, 2)).unwrap();
+        fs::remove_file(snapshot_path(file_path, 3)).unwrap();
+    }
+
+    /// Tests that a second `FileLock::acquire` on the same file blocks while the first is held,
+    /// and proceeds as soon as the first is dropped — the lock is released by the kernel, not by
+    /// any sidecar bookkeeping.
+    #[test]
+    fn test_file_lock_is_exclusive() {
+        let path = std::env::temp_dir().join(format!("it-test-lock-{}.txt", std::process::id()));
+        let file_path = path.to_str().unwrap().to_string();
+
+        let first = FileLock::acquire(&file_path).unwrap();
+
+        let waiter_file_path = file_path.clone();
+        let waiter = thread::spawn(move || FileLock::acquire(&waiter_file_path).unwrap());
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert!(!waiter.is_finished(), "second acquire should block while the first lock is held");
+
+        drop(first);
+        waiter.join().unwrap();
+    }
+
+    /// Tests that a pre-existing `.lock` sidecar left behind by a dead process (no live holder)
+    /// does not block acquisition — flock is tied to the holder's file descriptor, not the
+    /// sidecar's mere existence on disk.
+    #[test]
+    fn test_file_lock_ignores_stale_sidecar() {
+        let path = std::env::temp_dir().join(format!("it-test-stale-lock-{}.txt", std::process::id()));
+        let file_path = path.to_str().unwrap();
+        let lock_path = format!("{}.lock", file_path);
+        fs::write(&lock_path, b"").unwrap();
+
+        let lock = FileLock::acquire(file_path).unwrap();
+
+        drop(lock);
+        assert!(!Path::new(&lock_path).exists(), "clean release should unlink the lock sidecar");
+    }
+
+    /// Tests that a clean `FileLock` release removes the `.lock` sidecar entirely, so a batch
+    /// over many files doesn't leave one behind per file forever.
+    #[test]
+    fn test_file_lock_unlinks_sidecar_on_clean_release() {
+        let path = std::env::temp_dir().join(format!("it-test-lock-cleanup-{}.txt", std::process::id()));
+        let file_path = path.to_str().unwrap();
+        let lock_path = format!("{}.lock", file_path);
+
+        let lock = FileLock::acquire(file_path).unwrap();
+        assert!(Path::new(&lock_path).exists());
+
+        drop(lock);
+        assert!(!Path::new(&lock_path).exists());
+    }
+
+    /// Tests that `--dry-run` never acquires (and so never creates) a `.lock` sidecar, since it
+    /// doesn't touch the file at all.
+    #[test]
+    fn test_process_file_dry_run_does_not_create_lock_file() {
+        let path = std::env::temp_dir().join(format!("it-test-dry-run-lock-{}.txt", std::process::id()));
+        fs::write(&path, "Line 1\n").unwrap();
+        let file_path = path.to_str().unwrap();
+        let lock_path = format!("{}.lock", file_path);
+
+        let opts = Options {
+            append_text: Some("Appended".to_string()),
+            keep: 10,
+            dry_run: true,
+            ..Default::default()
+        };
+        process_file(file_path, &opts).unwrap();
+
+        assert!(!Path::new(&lock_path).exists(), "--dry-run must not create a lock sidecar");
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that `process_files` processes every file in the batch and reports per-file results
+    /// even when run with multiple worker threads.
+    #[test]
+    fn test_process_files_handles_every_file() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let paths: Vec<String> = (0..4)
+            .map(|i| dir.join(format!("it-test-batch-{}-{}.txt", pid, i)).to_str().unwrap().to_string())
+            .collect();
+        for path in &paths {
+            fs::write(path, "Line 1\n").unwrap();
+        }
+
+        let opts = Arc::new(Options {
+            append_text: Some("Appended".to_string()),
+            keep: 10,
+            ..Default::default()
+        });
+        let results = process_files(paths.clone(), 2, opts);
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|(_, r)| r.is_ok()));
+
+        for path in &paths {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(content.contains("Appended"));
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    /// Tests that `resolve_byte_offset` measures `--from-end` offsets back from the file length.
+    #[test]
+    fn test_resolve_byte_offset_from_end() {
+        assert_eq!(resolve_byte_offset(4, true, 10).unwrap(), 6);
+        assert!(resolve_byte_offset(11, true, 10).is_err());
+    }
+
+    /// Tests that `format_unix_time` renders Unix timestamps as human-readable UTC date/times.
+    #[test]
+    fn test_format_unix_time() {
+        assert_eq!(format_unix_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_unix_time(1_700_000_000), "2023-11-14 22:13:20");
+    }
+
+    /// Tests that byte-mode `--clear START,END` removes the inclusive range `[START, END]`.
+    #[test]
+    fn test_process_file_bytes_clear_range_is_inclusive() {
+        let path = std::env::temp_dir().join(format!("it-test-byte-clear-{}.bin", std::process::id()));
+        fs::write(&path, b"abcdef").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let opts = Options {
+            clear_range: Some((2, Some(3))),
+            keep: 10,
+            byte_mode: true,
+            ..Default::default()
+        };
+        process_file_bytes(file_path, &opts).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"adef");
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that byte-mode `--clear` rejects a start position beyond the end of the file rather
+    /// than silently clearing nothing, matching the line-oriented path's out-of-range check.
+    #[test]
+    fn test_process_file_bytes_clear_rejects_start_past_eof() {
+        let path = std::env::temp_dir().join(format!("it-test-byte-clear-eof-{}.bin", std::process::id()));
+        fs::write(&path, b"abcdef").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let opts = Options {
+            clear_range: Some((7, None)),
+            keep: 10,
+            byte_mode: true,
+            ..Default::default()
+        };
+
+        assert!(process_file_bytes(file_path, &opts).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that byte-mode insert splices text in at the given offset without touching the
+    /// rest of the file, unlike the line-oriented path.
+    #[test]
+    fn test_process_file_bytes_insert_splices_at_offset() {
+        let path = std::env::temp_dir().join(format!("it-test-byte-insert-{}.bin", std::process::id()));
+        fs::write(&path, b"abcdef").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let opts = Options {
+            insert_text: Some("XY".to_string()),
+            keep: 10,
+            byte_mode: true,
+            at: Some(3),
+            ..Default::default()
+        };
+        process_file_bytes(file_path, &opts).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"abcXYdef");
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that `--append` in byte mode lands at the end of the file regardless of `--at`,
+    /// rather than splicing in at the default (or given) offset like `--insert` does.
+    #[test]
+    fn test_process_file_bytes_append_goes_to_end() {
+        let path = std::env::temp_dir().join(format!("it-test-byte-append-{}.bin", std::process::id()));
+        fs::write(&path, b"abcdef").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let opts = Options {
+            append_text: Some("Z".to_string()),
+            keep: 10,
+            byte_mode: true,
+            at: Some(2),
+            ..Default::default()
+        };
+        process_file_bytes(file_path, &opts).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"abcdefZ");
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that `write_verified` succeeds once the on-disk bytes have been confirmed to match
+    /// what was written.
+    #[test]
+    fn test_write_verified_round_trips() {
+        let path = std::env::temp_dir().join(format!("it-test-verify-{}.txt", std::process::id()));
+
+        write_verified(&path, b"hello", true).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hello");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that `--append` with `--verify` goes through `commit` (read-back verified) rather
+    /// than the append-only fast path, and still produces the expected contents.
+    #[test]
+    fn test_process_file_append_with_verify() {
+        let path = std::env::temp_dir().join(format!("it-test-append-verify-{}.txt", std::process::id()));
+        fs::write(&path, "Line 1\n").unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let opts = Options {
+            append_text: Some("Appended".to_string()),
+            keep: 10,
+            verify: true,
+            ..Default::default()
+        };
+        process_file(file_path, &opts).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Line 1\nAppended\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Tests that `restore_snapshot` with no explicit version restores the most recent snapshot.
+    #[test]
+    fn test_restore_snapshot_restores_latest() {
+        let path = std::env::temp_dir().join(format!("it-test-restore-{}.txt", std::process::id()));
+        let file_path = path.to_str().unwrap();
+        fs::write(&path, b"v1").unwrap();
+        create_snapshot(file_path, 10).unwrap();
+        fs::write(&path, b"v2").unwrap();
+        create_snapshot(file_path, 10).unwrap();
+        fs::write(&path, b"corrupted").unwrap();
+
+        let restored = restore_snapshot(file_path, None).unwrap();
+
+        assert_eq!(restored, 2);
+        assert_eq!(fs::read(&path).unwrap(), b"v2");
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(snapshot_path(file_path, 1)).unwrap();
+        fs::remove_file(snapshot_path(file_path, 2)).unwrap();
+    }
 }
\ No newline at end of file